@@ -6,9 +6,19 @@ appropriate locations *at compile time*. This is akin to installing associated
 files during system package installation, but for `cargo install` or other
 build procedures.
 
-The four exposed functions take sets of paths within the crate source and copy
-those paths into the appropriate system location, as determined by the client
-project name and the choice of function.
+The exposed macros (`user_config!`, `user_data!`, `user_cache!`, `site_config!`,
+`site_data!`) take sets of paths within the crate source and copy those paths
+into the appropriate system location, as determined by the client project name
+and the choice of macro. Each one consults an `ASSOC_FILES_*_DIR` environment
+variable before falling back to the location `appdirs` computes, so packagers
+and CI can redirect an install without touching the build script.
+
+The [`run`] module's `run_config!`/`run_data!` macros are the runtime
+counterpart: they embed their files into the binary at compile time, so a
+prebuilt binary that never ran this crate's build script can still recover
+its configuration and data files on first access.
+
+[`run`]: run/index.html
 
 The functions can accept paths to files or directiories. Paths to files will
 place the file in the top level of the target. Paths to directories will keep
@@ -41,21 +51,67 @@ target. Their original paths within the crate source are not maintained.
 #![macro_export]
 
 extern crate appdirs;
+extern crate flate2;
+extern crate tar;
 extern crate walkdir;
+extern crate xz2;
 
 use std::fs::{
 	self,
 };
 use std::io::{
 	self,
+	Read,
+	Write,
 };
 use std::path::{
 	Path,
+	PathBuf,
+};
+use flate2::{
+	Compression,
+};
+use flate2::read::{
+	GzDecoder,
+};
+use flate2::write::{
+	GzEncoder,
+};
+use xz2::read::{
+	XzDecoder,
+};
+use xz2::write::{
+	XzEncoder,
 };
 use walkdir::{
 	WalkDir,
 };
 
+pub mod run;
+pub use run::{
+	Asset,
+	installed_name,
+	materialize,
+};
+
+/// Resolves an appdirs-computed base directory, letting an environment
+/// variable override it when present.
+///
+/// Standard-directory tooling (and the packagers/CI that drive it) expect an
+/// explicit override to win over whatever the platform otherwise computes, so
+/// each install macro checks its variable before falling back to the value
+/// `appdirs` returns.
+///
+/// Not part of the public API; exported only so the install macros can reach
+/// it from a caller's crate root.
+#[doc(hidden)]
+pub fn env_override(var: &str, computed: PathBuf) -> PathBuf {
+	match ::std::env::var(var) {
+		Ok(path) => PathBuf::from(path),
+		Err(_) => computed,
+	}
+}
+
 /// Installs directly to `$user_config/$crate/$version`.
 ///
 /// This macro takes a list of items, much like `vec![]`. The items given must
@@ -65,6 +121,19 @@ use walkdir::{
 /// If it is called without parameters, it simply returns the installation
 /// directory.
 ///
+/// Setting `ASSOC_FILES_CONFIG_DIR` in the build script's environment
+/// overrides the computed `appdirs` location, letting packagers and CI
+/// redirect the install without patching the build script.
+///
+/// This macro records a manifest of what it installs, so [`user_config_uninstall!`]
+/// can remove exactly those files later. Since configuration files are prone
+/// to being hand-edited after installation, an existing file that differs
+/// from the incoming one is left in place, and the incoming version is
+/// written alongside it as `name.ext.new` instead, the way system package
+/// managers preserve locally modified configuration.
+///
+/// [`user_config_uninstall!`]: macro.user_config_uninstall.html
+///
 /// # Examples
 ///
 /// ## Installation at Compile Time
@@ -107,17 +176,27 @@ macro_rules! user_config {
 		];
 		let cn = env!("CARGO_PKG_NAME");
 		let cv = env!("CARGO_PKG_VERSION");
-		let base = appdirs::user_config_dir(Some(cn), None, false).unwrap();
+		let base = env_override(
+			"ASSOC_FILES_CONFIG_DIR",
+			appdirs::user_config_dir(Some(cn), None, false).unwrap(),
+		);
 		let ver = base.join(cv);
-		install_files(&arr, &ver)
+		install_files_with(&arr, &ver, InstallOptions {
+			manifest: true,
+			preserve_existing: true,
+			..Default::default()
+		})
 	}};
 
 	() => {
-		appdirs::user_config_dir(
-			Some(env!("CARGO_PKG_NAME")),
-			None,
-			false,
-		).unwrap().join(env!("CARGO_PKG_VERSION"))
+		env_override(
+			"ASSOC_FILES_CONFIG_DIR",
+			appdirs::user_config_dir(
+				Some(env!("CARGO_PKG_NAME")),
+				None,
+				false,
+			).unwrap(),
+		).join(env!("CARGO_PKG_VERSION"))
 	};
 }
 
@@ -130,6 +209,15 @@ macro_rules! user_config {
 /// If it is called without parameters, it simply returns the installation
 /// directory.
 ///
+/// Setting `ASSOC_FILES_DATA_DIR` in the build script's environment overrides
+/// the computed `appdirs` location, letting packagers and CI redirect the
+/// install without patching the build script.
+///
+/// This macro records a manifest of what it installs, so [`user_data_uninstall!`]
+/// can remove exactly those files later.
+///
+/// [`user_data_uninstall!`]: macro.user_data_uninstall.html
+///
 /// # Examples
 ///
 /// ## Installation at Compile Time
@@ -172,20 +260,329 @@ macro_rules! user_data {
 		];
 		let cn = env!("CARGO_PKG_NAME");
 		let cv = env!("CARGO_PKG_VERSION");
-		let base = appdirs::user_data_dir(Some(cn), None, false).unwrap();
+		let base = env_override(
+			"ASSOC_FILES_DATA_DIR",
+			appdirs::user_data_dir(Some(cn), None, false).unwrap(),
+		);
+		let ver = base.join(cv);
+		install_files_with(&paths, &ver, InstallOptions {
+			manifest: true,
+			..Default::default()
+		})
+	}};
+
+	() => {
+		env_override(
+			"ASSOC_FILES_DATA_DIR",
+			appdirs::user_data_dir(
+				Some(env!("CARGO_PKG_NAME")),
+				None,
+				false,
+			).unwrap(),
+		).join(env!("CARGO_PKG_VERSION"))
+	};
+}
+
+/// Removes everything [`user_config!`] installed.
+///
+/// Reads the manifest [`user_config!`] left in `$user_config/$crate/$version`
+/// and deletes exactly the files it listed, pruning any directories that end
+/// up empty. A successful return carries the count of files removed.
+///
+/// [`user_config!`]: macro.user_config.html
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// user_config_uninstall!().unwrap();
+/// ```
+#[macro_export]
+macro_rules! user_config_uninstall {
+	() => {
+		uninstall_files(&user_config!())
+	};
+}
+
+/// Removes everything [`user_data!`] installed.
+///
+/// Reads the manifest [`user_data!`] left in `$user_data/$crate/$version` and
+/// deletes exactly the files it listed, pruning any directories that end up
+/// empty. A successful return carries the count of files removed.
+///
+/// [`user_data!`]: macro.user_data.html
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// user_data_uninstall!().unwrap();
+/// ```
+#[macro_export]
+macro_rules! user_data_uninstall {
+	() => {
+		uninstall_files(&user_data!())
+	};
+}
+
+/// Installs directly to `$user_cache/$crate/$version`.
+///
+/// This macro takes a list of items, much like `vec![]`. The items given must
+/// be usable as paths (strictly speaking, they must all satisfy `AsRef<Path>`),
+/// and will almost always be `&str` literals.
+///
+/// If it is called without parameters, it simply returns the installation
+/// directory.
+///
+/// Setting `ASSOC_FILES_CACHE_DIR` in the build script's environment
+/// overrides the computed `appdirs` location, letting packagers and CI
+/// redirect the install without patching the build script.
+///
+/// # Examples
+///
+/// ## Installation at Compile Time
+///
+/// For a source directory:
+///
+/// ```text
+/// sample/
+///   foo.txt
+///   data/
+///     bar.txt
+///     baz/
+///       quux.txt
+/// ```
+///
+/// then the build script should call
+///
+/// ```rust,ignore
+/// user_cache!["sample/foo.txt", "sample/data"];
+/// ```
+///
+/// to copy `sample/foo.txt` and the contents below `sample/data/` into the
+/// current user's cache directory. With this call, `foo.txt`, `bar.txt`, and
+/// `baz/` will be siblings in the installed location.
+///
+/// ## Accessing at Run Time
+///
+/// In your crate's logic, the installed files can be accessed like so:
+///
+/// ```rust,ignore
+/// let cache_dir: PathBuf = user_cache!();
+/// let foo = File::open(cache_dir.join("foo.txt")).unwrap();
+/// let quux = File::open(cache_dir.join("baz").join("quux.txt")).unwrap();
+/// ```
+#[macro_export]
+macro_rules! user_cache {
+	($($f:expr),+) => {{
+		let paths = [
+			$($f),+
+		];
+		let cn = env!("CARGO_PKG_NAME");
+		let cv = env!("CARGO_PKG_VERSION");
+		let base = env_override(
+			"ASSOC_FILES_CACHE_DIR",
+			appdirs::user_cache_dir(Some(cn), None).unwrap(),
+		);
 		let ver = base.join(cv);
-		install_files(&paths, &ver)
+		install_files_with(&paths, &ver, InstallOptions {
+			manifest: true,
+			..Default::default()
+		})
 	}};
 
 	() => {
-		appdirs::user_data_dir(
-			Some(env!("CARGO_PKG_NAME")),
-			None,
-			false,
-		).unwrap().join(env!("CARGO_PKG_VERSION"))
+		env_override(
+			"ASSOC_FILES_CACHE_DIR",
+			appdirs::user_cache_dir(
+				Some(env!("CARGO_PKG_NAME")),
+				None,
+			).unwrap(),
+		).join(env!("CARGO_PKG_VERSION"))
 	};
 }
 
+/// Installs directly to `$site_config/$crate/$version`.
+///
+/// This macro takes a list of items, much like `vec![]`. The items given must
+/// be usable as paths (strictly speaking, they must all satisfy `AsRef<Path>`),
+/// and will almost always be `&str` literals.
+///
+/// If it is called without parameters, it simply returns the installation
+/// directory.
+///
+/// This is the read-only, shared-install counterpart to [`user_config!`]: it
+/// targets the platform's site-wide configuration location (e.g. `/etc` on
+/// Linux), for assets a build script stages for every user of the machine
+/// rather than just the user running `cargo build`.
+///
+/// Setting `ASSOC_FILES_SITE_CONFIG_DIR` in the build script's environment
+/// overrides the computed `appdirs` location, letting packagers and CI
+/// redirect the site-wide install independently of [`user_config!`]'s own
+/// `ASSOC_FILES_CONFIG_DIR` override.
+///
+/// Like [`user_config!`], this macro records a manifest of what it installs
+/// and leaves a differing existing file in place, writing the incoming
+/// version alongside it as `name.ext.new` instead of overwriting it.
+///
+/// [`user_config!`]: macro.user_config.html
+///
+/// # Examples
+///
+/// ## Installation at Compile Time
+///
+/// For a source directory:
+///
+/// ```text
+/// sample/
+///   foo.txt
+///   data/
+///     bar.txt
+///     baz/
+///       quux.txt
+/// ```
+///
+/// then the build script should call
+///
+/// ```rust,ignore
+/// site_config!["sample/foo.txt", "sample/data"];
+/// ```
+///
+/// to copy `sample/foo.txt` and the contents below `sample/data/` into the
+/// site-wide configuration directory. With this call, `foo.txt`, `bar.txt`,
+/// and `baz/` will be siblings in the installed location.
+///
+/// ## Accessing at Run Time
+///
+/// In your crate's logic, the installed files can be accessed like so:
+///
+/// ```rust,ignore
+/// let config_dir: PathBuf = site_config!();
+/// let foo = File::open(config_dir.join("foo.txt")).unwrap();
+/// let quux = File::open(config_dir.join("baz").join("quux.txt")).unwrap();
+/// ```
+#[macro_export]
+macro_rules! site_config {
+	($($f:expr),+) => {{
+		let arr = [
+			$($f),+
+		];
+		let cn = env!("CARGO_PKG_NAME");
+		let cv = env!("CARGO_PKG_VERSION");
+		let base = env_override(
+			"ASSOC_FILES_SITE_CONFIG_DIR",
+			appdirs::site_config_dir(Some(cn), None, false).unwrap(),
+		);
+		let ver = base.join(cv);
+		install_files_with(&arr, &ver, InstallOptions {
+			manifest: true,
+			preserve_existing: true,
+			..Default::default()
+		})
+	}};
+
+	() => {
+		env_override(
+			"ASSOC_FILES_SITE_CONFIG_DIR",
+			appdirs::site_config_dir(
+				Some(env!("CARGO_PKG_NAME")),
+				None,
+				false,
+			).unwrap(),
+		).join(env!("CARGO_PKG_VERSION"))
+	};
+}
+
+/// Installs directly to `$site_data/$crate/$version`.
+///
+/// This macro takes a list of items, much like `vec![]`. The items given must
+/// be usable as paths (strictly speaking, they must all satisfy `AsRef<Path>`),
+/// and will almost always be `&str` literals.
+///
+/// If it is called without parameters, it simply returns the installation
+/// directory.
+///
+/// This is the read-only, shared-install counterpart to [`user_data!`]: it
+/// targets the platform's site-wide data location (e.g. `/usr/share` on
+/// Linux), for assets a build script stages for every user of the machine
+/// rather than just the user running `cargo build`.
+///
+/// Setting `ASSOC_FILES_SITE_DATA_DIR` in the build script's environment
+/// overrides the computed `appdirs` location, letting packagers and CI
+/// redirect the site-wide install independently of [`user_data!`]'s own
+/// `ASSOC_FILES_DATA_DIR` override.
+///
+/// [`user_data!`]: macro.user_data.html
+///
+/// # Examples
+///
+/// ## Installation at Compile Time
+///
+/// For a source directory:
+///
+/// ```text
+/// sample/
+///   foo.txt
+///   data/
+///     bar.txt
+///     baz/
+///       quux.txt
+/// ```
+///
+/// then the build script should call
+///
+/// ```rust,ignore
+/// site_data!["sample/foo.txt", "sample/data"];
+/// ```
+///
+/// to copy `sample/foo.txt` and the contents below `sample/data/` into the
+/// site-wide data directory. With this call, `foo.txt`, `bar.txt`, and
+/// `baz/` will be siblings in the installed location.
+///
+/// ## Accessing at Run Time
+///
+/// In your crate's logic, the installed files can be accessed like so:
+///
+/// ```rust,ignore
+/// let data_dir: PathBuf = site_data!();
+/// let foo = File::open(data_dir.join("foo.txt")).unwrap();
+/// let quux = File::open(data_dir.join("baz").join("quux.txt")).unwrap();
+/// ```
+#[macro_export]
+macro_rules! site_data {
+	($($f:expr),+) => {{
+		let paths = [
+			$($f),+
+		];
+		let cn = env!("CARGO_PKG_NAME");
+		let cv = env!("CARGO_PKG_VERSION");
+		let base = env_override(
+			"ASSOC_FILES_SITE_DATA_DIR",
+			appdirs::site_data_dir(Some(cn), None, false).unwrap(),
+		);
+		let ver = base.join(cv);
+		install_files_with(&paths, &ver, InstallOptions {
+			manifest: true,
+			..Default::default()
+		})
+	}};
+
+	() => {
+		env_override(
+			"ASSOC_FILES_SITE_DATA_DIR",
+			appdirs::site_data_dir(
+				Some(env!("CARGO_PKG_NAME")),
+				None,
+				false,
+			).unwrap(),
+		).join(env!("CARGO_PKG_VERSION"))
+	};
+}
+
+/// Name of the manifest file that [`install_files_manifest`] leaves in a
+/// destination directory, recording what it installed there.
+///
+/// [`install_files_manifest`]: fn.install_files_manifest.html
+const MANIFEST_NAME: &str = ".assoc_files_manifest";
+
 /// Installs the given files into a destination within the system.
 ///
 /// This function assumes that all files named in the paths array should be
@@ -194,6 +591,12 @@ macro_rules! user_data {
 ///
 /// A successful return carries the count of installed files.
 ///
+/// This behaves like [`install_files_manifest`] with `manifest` set to
+/// `false`: it never writes a manifest, so existing callers are unaffected by
+/// its addition.
+///
+/// [`install_files_manifest`]: fn.install_files_manifest.html
+///
 /// # Examples
 ///
 /// ## Installation at Compile Time
@@ -231,10 +634,106 @@ macro_rules! user_data {
 /// given in your build script using `std::fs`.
 pub fn install_files<P>(paths: &[P], dest: &Path) -> io::Result<usize>
 	where P: AsRef<Path> {
+	install_files_core(paths, dest, false, false, |_| true)
+}
+
+/// Installs the given files into a destination within the system, optionally
+/// recording a manifest of what was installed.
+///
+/// Behaves exactly like [`install_files`], except that when `manifest` is
+/// `true` it also writes [`MANIFEST_NAME`] into `dest`: one line for the
+/// destination root, followed by one line per relative path installed below
+/// it. [`uninstall_files`] reads this manifest back to remove exactly what
+/// was installed, without disturbing anything else that may have since been
+/// added to `dest`.
+///
+/// [`install_files`]: fn.install_files.html
+/// [`uninstall_files`]: fn.uninstall_files.html
+pub fn install_files_manifest<P>(paths: &[P], dest: &Path, manifest: bool) -> io::Result<usize>
+	where P: AsRef<Path> {
+	install_files_core(paths, dest, manifest, false, |_| true)
+}
+
+/// Installs the given files into a destination within the system, skipping
+/// any entry for which `keep` returns `false`.
+///
+/// Behaves exactly like [`install_files`], except that `keep` is evaluated
+/// against each entry's path relative to the walked root (so for a directory
+/// source `"sample/data"`, an entry at `sample/data/tests/foo.tt` is offered
+/// to `keep` as `tests/foo.tt`) before it is copied. This lets a build script
+/// ship, say, only `*.tt` templates while skipping `.gitignore`, editor swap
+/// files, or a whole `tests/` subtree. Directories that end up holding no
+/// kept files are not created.
+///
+/// [`install_files`]: fn.install_files.html
+pub fn install_files_filtered<P, F>(paths: &[P], dest: &Path, keep: F) -> io::Result<usize>
+	where P: AsRef<Path>, F: Fn(&Path) -> bool {
+	install_files_core(paths, dest, false, false, keep)
+}
+
+/// Options controlling an [`install_files_with`] call.
+///
+/// Construct one with [`Default::default`] and adjust the fields that matter.
+///
+/// [`install_files_with`]: fn.install_files_with.html
+pub struct InstallOptions<F = fn(&Path) -> bool> where F: Fn(&Path) -> bool {
+	/// Write a [`MANIFEST_NAME`] manifest into the destination, as
+	/// [`install_files_manifest`] does.
+	///
+	/// [`install_files_manifest`]: fn.install_files_manifest.html
+	pub manifest: bool,
+	/// When a destination file already exists and differs from the source,
+	/// leave it alone and write the incoming version beside it as
+	/// `name.ext.new` instead of overwriting it, the way system package
+	/// managers preserve locally modified configuration.
+	pub preserve_existing: bool,
+	/// Skip any entry for which this returns `false`, as
+	/// [`install_files_filtered`] does.
+	///
+	/// [`install_files_filtered`]: fn.install_files_filtered.html
+	pub keep: F,
+}
+
+impl Default for InstallOptions {
+	fn default() -> Self {
+		InstallOptions {
+			manifest: false,
+			preserve_existing: false,
+			keep: |_| true,
+		}
+	}
+}
+
+/// Installs the given files into a destination within the system, as
+/// configured by `opts`.
+///
+/// This is the general entry point: [`install_files`],
+/// [`install_files_manifest`], and [`install_files_filtered`] each behave
+/// like a call to it with the matching [`InstallOptions`] fields set.
+///
+/// [`install_files`]: fn.install_files.html
+/// [`install_files_manifest`]: fn.install_files_manifest.html
+/// [`install_files_filtered`]: fn.install_files_filtered.html
+/// [`InstallOptions`]: struct.InstallOptions.html
+pub fn install_files_with<P, F>(paths: &[P], dest: &Path, opts: InstallOptions<F>) -> io::Result<usize>
+	where P: AsRef<Path>, F: Fn(&Path) -> bool {
+	install_files_core(paths, dest, opts.manifest, opts.preserve_existing, opts.keep)
+}
+
+/// Shared implementation backing [`install_files`], [`install_files_manifest`],
+/// [`install_files_filtered`], and [`install_files_with`].
+///
+/// [`install_files`]: fn.install_files.html
+/// [`install_files_manifest`]: fn.install_files_manifest.html
+/// [`install_files_filtered`]: fn.install_files_filtered.html
+/// [`install_files_with`]: fn.install_files_with.html
+fn install_files_core<P, F>(paths: &[P], dest: &Path, manifest: bool, preserve_existing: bool, keep: F) -> io::Result<usize>
+	where P: AsRef<Path>, F: Fn(&Path) -> bool {
 	if !dest.exists() {
 		fs::create_dir_all(&dest)?;
 	}
 	let mut count = 0;
+	let mut installed: Vec<PathBuf> = Vec::new();
 	for p in paths {
 		let p = p.as_ref();
 		//  For any directories, their *contents* are copied as-is into the
@@ -247,29 +746,268 @@ pub fn install_files<P>(paths: &[P], dest: &Path) -> io::Result<usize>
 				let full_path = entry.path();
 				/// Relative path inside the source root dir, with p stripped.
 				let inner_path = full_path.strip_prefix(p).unwrap_or(full_path);
-				/// Final target: destination/entry.
-				let dest_path = dest.join(inner_path);
-				//  If the entry's full path (in the source location) is a
-				//  directory, make a corresponding directory at target/inner
-				if full_path.is_dir() {
-					fs::create_dir_all(dest_path)?;
-				}
-					//  Otherwise, if the full path in source is a file, copy it
-					//  into the target location.
-					else if full_path.is_file() {
-						fs::copy(full_path, dest_path)?;
+				//  Only files are ever copied; directories are created lazily
+				//  below, as a side effect of copying a kept file into them,
+				//  so a directory that ends up with no kept files is never
+				//  created empty.
+				if full_path.is_file() && keep(inner_path) {
+					let dest_path = dest.join(inner_path);
+					if let Some(parent) = dest_path.parent() {
+						fs::create_dir_all(parent)?;
+					}
+					if let Some(installed_path) = copy_entry(full_path, &dest_path, preserve_existing)? {
 						count += 1;
+						installed.push(inner_path.with_file_name(
+							installed_path.file_name().unwrap(),
+						));
 					}
+				}
 			}
 		}
 			//  For any files, the file gets copied directly into the target root.
 			else if p.is_file() {
 				let inner_path = p.file_name().unwrap();
-				let dest_path = dest.join(inner_path);
-				fs::copy(p, dest_path)?;
+				if keep(Path::new(inner_path)) {
+					let dest_path = dest.join(inner_path);
+					if let Some(installed_path) = copy_entry(p, &dest_path, preserve_existing)? {
+						count += 1;
+						installed.push(Path::new(installed_path.file_name().unwrap()).to_path_buf());
+					}
+				}
+			}
+	}
+	if manifest {
+		let mut lines = vec![dest.display().to_string()];
+		lines.extend(installed.into_iter().map(|p| p.display().to_string()));
+		fs::write(dest.join(MANIFEST_NAME), lines.join("\n"))?;
+	}
+	Ok(count)
+}
+
+/// Copies `src` to `dest_path`, honoring `preserve_existing`.
+///
+/// When `preserve_existing` is set and `dest_path` already exists with
+/// different contents than `src`, `src` is written beside it as
+/// `dest_path` with `.new` appended to the file name, and that path is
+/// returned; an existing file with identical contents is left untouched and
+/// `None` is returned, since nothing was installed. Otherwise `src` is
+/// copied straight to `dest_path`, which is returned.
+///
+/// Either way, `fs::copy` carries over `src`'s permission bits (and, on
+/// Unix, its executable bit) to the destination on its own, which is what
+/// keeps installed assets such as hook scripts runnable.
+fn copy_entry(src: &Path, dest_path: &Path, preserve_existing: bool) -> io::Result<Option<PathBuf>> {
+	if preserve_existing && dest_path.exists() {
+		if fs::read(dest_path)? == fs::read(src)? {
+			return Ok(None);
+		}
+		let mut new_name = dest_path.file_name().unwrap().to_os_string();
+		new_name.push(".new");
+		let new_path = dest_path.with_file_name(new_name);
+		fs::copy(src, &new_path)?;
+		return Ok(Some(new_path));
+	}
+	fs::copy(src, dest_path)?;
+	Ok(Some(dest_path.to_path_buf()))
+}
+
+/// Removes everything that a manifest-writing install (see
+/// [`install_files_manifest`]) placed in `dest`, then prunes any directories
+/// that are left empty.
+///
+/// Reads `dest`'s [`MANIFEST_NAME`] file, deletes every relative path it
+/// lists, deletes the manifest itself, and finally sweeps the manifest's
+/// parent directories deepest-first, removing any that are now empty. A
+/// directory that still holds files the installer never wrote (or that
+/// another install added since) is left alone, since `remove_dir` only
+/// succeeds on an empty directory.
+///
+/// A successful return carries the count of files removed.
+///
+/// [`install_files_manifest`]: fn.install_files_manifest.html
+pub fn uninstall_files(dest: &Path) -> io::Result<usize> {
+	let manifest_path = dest.join(MANIFEST_NAME);
+	let contents = fs::read_to_string(&manifest_path)?;
+	let mut lines = contents.lines();
+	//  The first line is the destination root the manifest was written for;
+	//  the files below are already rooted at `dest`, so it is not needed to
+	//  locate them, only kept for a human reading the manifest by hand.
+	let _root = lines.next();
+
+	let mut count = 0;
+	let mut dirs: Vec<PathBuf> = Vec::new();
+	for rel in lines {
+		let path = dest.join(rel);
+		if path.is_file() {
+			fs::remove_file(&path)?;
+			count += 1;
+		}
+		let mut ancestor = path.parent();
+		while let Some(dir) = ancestor {
+			if dir == dest {
+				break;
+			}
+			dirs.push(dir.to_path_buf());
+			ancestor = dir.parent();
+		}
+	}
+	fs::remove_file(&manifest_path)?;
+
+	//  Prune now-empty directories bottom-up: sort deepest-first so a
+	//  directory's children are removed (or found non-empty) before we try
+	//  the directory itself.
+	dirs.sort_by_key(|d| ::std::cmp::Reverse(d.components().count()));
+	dirs.dedup();
+	for dir in dirs {
+		let _ = fs::remove_dir(&dir);
+	}
+	Ok(count)
+}
+
+/// A `tar` writer, optionally wrapped in a compressor.
+///
+/// `flate2`'s and `xz2`'s encoders need their own `finish()` called to flush
+/// the compressor's trailer, which a plain `Write` impl cannot express, so
+/// this enum carries the concrete writer through to [`pack_files`] instead of
+/// a trait object.
+///
+/// [`pack_files`]: fn.pack_files.html
+enum ArchiveWriter {
+	Plain(fs::File),
+	Gzip(GzEncoder<fs::File>),
+	Xz(XzEncoder<fs::File>),
+}
+
+impl Write for ArchiveWriter {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		match *self {
+			ArchiveWriter::Plain(ref mut w) => w.write(buf),
+			ArchiveWriter::Gzip(ref mut w) => w.write(buf),
+			ArchiveWriter::Xz(ref mut w) => w.write(buf),
+		}
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		match *self {
+			ArchiveWriter::Plain(ref mut w) => w.flush(),
+			ArchiveWriter::Gzip(ref mut w) => w.flush(),
+			ArchiveWriter::Xz(ref mut w) => w.flush(),
+		}
+	}
+}
+
+impl ArchiveWriter {
+	/// Flushes any compressor trailer and closes the underlying file.
+	fn finish(self) -> io::Result<()> {
+		match self {
+			ArchiveWriter::Plain(mut w) => w.flush(),
+			ArchiveWriter::Gzip(w) => w.finish().map(|_| ()),
+			ArchiveWriter::Xz(w) => w.finish().map(|_| ()),
+		}
+	}
+}
+
+/// Opens `archive` for reading, wrapping it in a decompressor chosen by its
+/// `.tar.gz` / `.tar.xz` / `.tar` extension.
+fn archive_reader(archive: &Path) -> io::Result<Box<dyn Read>> {
+	let file = fs::File::open(archive)?;
+	let name = archive.to_string_lossy();
+	Ok(if name.ends_with(".tar.gz") {
+		Box::new(GzDecoder::new(file))
+	}
+		else if name.ends_with(".tar.xz") {
+			Box::new(XzDecoder::new(file))
+		}
+		else {
+			Box::new(file)
+		})
+}
+
+/// Creates `archive` for writing, wrapping it in a compressor chosen by its
+/// `.tar.gz` / `.tar.xz` / `.tar` extension.
+fn archive_writer(archive: &Path) -> io::Result<ArchiveWriter> {
+	let file = fs::File::create(archive)?;
+	let name = archive.to_string_lossy();
+	Ok(if name.ends_with(".tar.gz") {
+		ArchiveWriter::Gzip(GzEncoder::new(file, Compression::default()))
+	}
+		else if name.ends_with(".tar.xz") {
+			ArchiveWriter::Xz(XzEncoder::new(file, 6))
+		}
+		else {
+			ArchiveWriter::Plain(file)
+		})
+}
+
+/// Extracts a `.tar`, `.tar.gz`, or `.tar.xz` archive into `dest`.
+///
+/// Mirrors the "strip the root, keep interior structure" rule that
+/// [`install_files`] applies to directory sources: entries are recreated
+/// under `dest` using the path they were stored at in the archive, which
+/// [`pack_files`] already roots at the packed source, not at the original
+/// filesystem location.
+///
+/// A successful return carries the count of files extracted.
+///
+/// [`install_files`]: fn.install_files.html
+/// [`pack_files`]: fn.pack_files.html
+pub fn install_archive(archive: &Path, dest: &Path) -> io::Result<usize> {
+	if !dest.exists() {
+		fs::create_dir_all(&dest)?;
+	}
+	let mut archive = tar::Archive::new(archive_reader(archive)?);
+	let mut count = 0;
+	for entry in archive.entries()? {
+		let mut entry = entry?;
+		if entry.header().entry_type().is_file() {
+			let inner_path = entry.path()?.into_owned();
+			let dest_path = dest.join(&inner_path);
+			if let Some(parent) = dest_path.parent() {
+				fs::create_dir_all(parent)?;
+			}
+			entry.unpack(&dest_path)?;
+			count += 1;
+		}
+	}
+	Ok(count)
+}
+
+/// Packs the given files into a single `.tar`, `.tar.gz`, or `.tar.xz`
+/// archive, chosen by `archive`'s extension.
+///
+/// Takes the same `paths` a directory-or-file list as [`install_files`], and
+/// applies the same "strip the root, keep interior structure" rule when
+/// storing directory contents, so the result is exactly what
+/// [`install_archive`] would later extract. This lets a crate commit one
+/// compressed blob instead of hundreds of loose data files.
+///
+/// A successful return carries the count of files packed.
+///
+/// [`install_files`]: fn.install_files.html
+/// [`install_archive`]: fn.install_archive.html
+pub fn pack_files<P>(paths: &[P], archive: &Path) -> io::Result<usize>
+	where P: AsRef<Path> {
+	let mut builder = tar::Builder::new(archive_writer(archive)?);
+	let mut count = 0;
+	for p in paths {
+		let p = p.as_ref();
+		if p.is_dir() {
+			for entry in WalkDir::new(p).into_iter().filter_map(|e| e.ok()) {
+				let full_path = entry.path();
+				let inner_path = full_path.strip_prefix(p).unwrap_or(full_path);
+				if full_path.is_file() {
+					builder.append_path_with_name(full_path, inner_path)?;
+					count += 1;
+				}
+			}
+		}
+			else if p.is_file() {
+				let inner_path = p.file_name().unwrap();
+				builder.append_path_with_name(p, inner_path)?;
 				count += 1;
 			}
 	}
+	builder.into_inner()?.finish()?;
 	Ok(count)
 }
 
@@ -298,4 +1036,150 @@ mod tests {
 
 		fs::remove_dir_all(target).unwrap();
 	}
+
+	#[test]
+	fn manifest_round_trip() {
+		let target = Path::new("target/tmp_manifest");
+		if target.exists() {
+			fs::remove_dir_all(target).unwrap();
+		}
+		fs::create_dir_all(target).unwrap();
+
+		let res = install_files_manifest(&["data/foo.txt", "data/data"], &target.to_path_buf(), true);
+		assert!(res.is_ok());
+		assert_eq!(res.ok(), Some(3));
+		assert!(target.join(MANIFEST_NAME).exists());
+
+		let removed = uninstall_files(&target.to_path_buf());
+		assert!(removed.is_ok());
+		assert_eq!(removed.ok(), Some(3));
+		assert!(!target.join(MANIFEST_NAME).exists());
+		assert!(!target.join("foo.txt").exists());
+
+		if target.exists() {
+			fs::remove_dir_all(target).unwrap();
+		}
+	}
+
+	#[test]
+	fn uninstall_prunes_nested_directories() {
+		//  Built by hand rather than through `install_files_manifest`, so the
+		//  manifest can name a file nested two directories deep (`a/b/c.txt`)
+		//  and exercise pruning past the immediate parent.
+		let target = Path::new("target/tmp_nested_uninstall");
+		if target.exists() {
+			fs::remove_dir_all(target).unwrap();
+		}
+		fs::create_dir_all(target.join("a").join("b")).unwrap();
+		fs::write(target.join("a").join("b").join("c.txt"), b"hi").unwrap();
+		fs::write(
+			target.join(MANIFEST_NAME),
+			format!("{}\na/b/c.txt", target.display()),
+		).unwrap();
+
+		let removed = uninstall_files(&target.to_path_buf());
+		assert!(removed.is_ok());
+		assert_eq!(removed.ok(), Some(1));
+		assert!(!target.join("a").join("b").join("c.txt").exists());
+		assert!(!target.join("a").join("b").exists());
+		assert!(!target.join("a").exists());
+		assert!(target.exists());
+
+		fs::remove_dir_all(target).unwrap();
+	}
+
+	#[test]
+	fn install_filtered_skips_and_prunes() {
+		let target = Path::new("target/tmp_filtered");
+		if target.exists() {
+			fs::remove_dir_all(target).unwrap();
+		}
+		fs::create_dir_all(target).unwrap();
+
+		let res = install_files_filtered(
+			&["data/foo.txt", "data/data"],
+			&target.to_path_buf(),
+			|p| !p.starts_with("baz"),
+		);
+		assert!(res.is_ok());
+		assert_eq!(res.ok(), Some(2));
+		assert!(target.join("foo.txt").exists());
+		assert!(target.join("bar.txt").exists());
+		assert!(!target.join("baz").exists());
+
+		fs::remove_dir_all(target).unwrap();
+	}
+
+	#[test]
+	fn preserve_existing_writes_dot_new() {
+		let target = Path::new("target/tmp_preserve");
+		if target.exists() {
+			fs::remove_dir_all(target).unwrap();
+		}
+		fs::create_dir_all(target).unwrap();
+		fs::write(target.join("foo.txt"), "locally edited").unwrap();
+
+		let res = install_files_with(&["data/foo.txt"], &target.to_path_buf(), InstallOptions {
+			preserve_existing: true,
+			..Default::default()
+		});
+		assert!(res.is_ok());
+		assert_eq!(res.ok(), Some(1));
+		assert_eq!(fs::read_to_string(target.join("foo.txt")).unwrap(), "locally edited");
+		assert!(target.join("foo.txt.new").exists());
+
+		fs::remove_dir_all(target).unwrap();
+	}
+
+	#[test]
+	fn pack_and_install_archive_round_trip() {
+		let archive = Path::new("target/tmp_archive.tar.gz");
+		let target = Path::new("target/tmp_unarchived");
+		for path in &[archive, target] {
+			if path.exists() {
+				if path.is_dir() {
+					fs::remove_dir_all(path).unwrap();
+				}
+					else {
+						fs::remove_file(path).unwrap();
+					}
+			}
+		}
+
+		let packed = pack_files(&["data/foo.txt", "data/data"], &archive.to_path_buf());
+		assert!(packed.is_ok());
+		assert_eq!(packed.ok(), Some(3));
+
+		let installed = install_archive(&archive.to_path_buf(), &target.to_path_buf());
+		assert!(installed.is_ok());
+		assert_eq!(installed.ok(), Some(3));
+		for file in &["foo.txt", "bar.txt", "baz/quux.txt"] {
+			assert!(target.join(file).exists());
+		}
+
+		fs::remove_file(archive).unwrap();
+		fs::remove_dir_all(target).unwrap();
+	}
+
+	#[test]
+	fn materialize_writes_missing_and_skips_existing() {
+		let target = Path::new("target/tmp_materialize");
+		if target.exists() {
+			fs::remove_dir_all(target).unwrap();
+		}
+
+		let assets = [
+			run::Asset { path: "foo.txt", bytes: b"fresh" },
+			run::Asset { path: "nested/bar.txt", bytes: b"also fresh" },
+		];
+		run::materialize(target, &assets).unwrap();
+		assert_eq!(fs::read_to_string(target.join("foo.txt")).unwrap(), "fresh");
+		assert_eq!(fs::read_to_string(target.join("nested/bar.txt")).unwrap(), "also fresh");
+
+		fs::write(target.join("foo.txt"), "locally edited").unwrap();
+		run::materialize(target, &assets).unwrap();
+		assert_eq!(fs::read_to_string(target.join("foo.txt")).unwrap(), "locally edited");
+
+		fs::remove_dir_all(target).unwrap();
+	}
 }
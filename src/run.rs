@@ -0,0 +1,147 @@
+/*! Runtime access to installed files
+
+`user_config!` and `user_data!` (and their `site_*`/`user_cache!` siblings)
+install files from the build script, which only runs when the client crate is
+built from source. A binary installed with `cargo install --path` from a
+prebuilt artifact, or otherwise shipped without ever invoking `cargo build` on
+the target machine, would find nothing at those locations.
+
+The macros in this module, `run_config!` and `run_data!`, close that gap: they
+take a list of file paths, like their build-time counterparts but without
+directory arguments, and the listed files are embedded into the binary at
+compile time with `include_bytes!` rather than copied out during a build
+script. `include_bytes!` resolves each path relative to the source file that
+invokes the macro, not the crate root. The first time the macro runs against
+a missing destination, it writes the embedded bytes out; every call after
+that finds the files already there and does nothing further. Either way, the
+macro returns, inside an `io::Result`, the same `PathBuf` that
+`user_config!()`/`user_data!()` would.
+!*/
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A single file embedded into the binary at compile time.
+///
+/// `run_config!`/`run_data!` build an array of these, one per path given to
+/// the macro, using `include_bytes!` on each.
+pub struct Asset {
+	/// The path the file is materialized at, relative to the installation
+	/// directory; missing parent directories are created by [`materialize`].
+	/// `run_config!`/`run_data!` set this to the file's name alone, not its
+	/// path within the crate source, matching the layout
+	/// `user_config!`/`user_data!` install to at build time (where a file
+	/// argument is placed at the top level of the destination).
+	///
+	/// [`materialize`]: fn.materialize.html
+	pub path: &'static str,
+	/// The file's contents, embedded into the binary.
+	pub bytes: &'static [u8],
+}
+
+/// Strips `path` down to its file name, the layout `install_files` gives a
+/// file argument.
+///
+/// Not part of the public API; exported only so `run_config!`/`run_data!`
+/// can reach it from a caller's crate root.
+#[doc(hidden)]
+pub fn installed_name(path: &'static str) -> &'static str {
+	Path::new(path).file_name().and_then(|f| f.to_str()).unwrap()
+}
+
+/// Writes out any `asset` whose `path` does not already exist under `dir`.
+///
+/// Existing files are left untouched; only a missing one is materialized
+/// from its embedded bytes.
+pub fn materialize(dir: &Path, assets: &[Asset]) -> io::Result<()> {
+	for asset in assets {
+		let dest = dir.join(asset.path);
+		if dest.exists() {
+			continue;
+		}
+		if let Some(parent) = dest.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		fs::write(dest, asset.bytes)?;
+	}
+	Ok(())
+}
+
+/// Returns `$user_config/$crate/$version`, materializing the given files into
+/// it from bytes embedded in the binary if they are not already present.
+///
+/// Takes the same kind of path list as [`user_config!`], but each path is
+/// read with `include_bytes!` at compile time, so it must exist in the
+/// crate's source tree rather than merely at build-script run time, and,
+/// unlike [`user_config!`], every entry must name a file: `include_bytes!`
+/// cannot embed a directory, so a directory argument that `user_config!`
+/// would accept fails to compile here. Each file is materialized under its
+/// bare file name, the same top-level layout `user_config!` installs a file
+/// argument to, so a path looked up through `user_config!()` resolves the
+/// same way after either macro runs. This is what lets the files be
+/// recovered on a machine that never ran the build script at all.
+///
+/// [`user_config!`]: macro.user_config.html
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let config_dir = run_config!["sample/foo.txt"];
+/// let foo = File::open(config_dir.unwrap().join("foo.txt")).unwrap();
+/// ```
+#[macro_export]
+macro_rules! run_config {
+	($($f:expr),+) => {{
+		let dir = env_override(
+			"ASSOC_FILES_CONFIG_DIR",
+			appdirs::user_config_dir(Some(env!("CARGO_PKG_NAME")), None, false).unwrap(),
+		).join(env!("CARGO_PKG_VERSION"));
+		let assets = [
+			$(Asset {
+				path: installed_name($f),
+				bytes: include_bytes!($f),
+			}),+
+		];
+		materialize(&dir, &assets).map(|_| dir)
+	}};
+}
+
+/// Returns `$user_data/$crate/$version`, materializing the given files into
+/// it from bytes embedded in the binary if they are not already present.
+///
+/// Takes the same kind of path list as [`user_data!`], but each path is read
+/// with `include_bytes!` at compile time, so it must exist in the crate's
+/// source tree rather than merely at build-script run time, and, unlike
+/// [`user_data!`], every entry must name a file: `include_bytes!` cannot
+/// embed a directory, so a directory argument that `user_data!` would accept
+/// fails to compile here. Each file is materialized under its bare file
+/// name, the same top-level layout `user_data!` installs a file argument to,
+/// so a path looked up through `user_data!()` resolves the same way after
+/// either macro runs. This is what lets the files be recovered on a machine
+/// that never ran the build script at all.
+///
+/// [`user_data!`]: macro.user_data.html
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let data_dir = run_data!["sample/foo.txt"];
+/// let foo = File::open(data_dir.unwrap().join("foo.txt")).unwrap();
+/// ```
+#[macro_export]
+macro_rules! run_data {
+	($($f:expr),+) => {{
+		let dir = env_override(
+			"ASSOC_FILES_DATA_DIR",
+			appdirs::user_data_dir(Some(env!("CARGO_PKG_NAME")), None, false).unwrap(),
+		).join(env!("CARGO_PKG_VERSION"));
+		let assets = [
+			$(Asset {
+				path: installed_name($f),
+				bytes: include_bytes!($f),
+			}),+
+		];
+		materialize(&dir, &assets).map(|_| dir)
+	}};
+}
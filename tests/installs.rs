@@ -38,3 +38,76 @@ fn user_data_macro() {
 	}
 	remove_dir_all(target_dir).unwrap();
 }
+
+#[test]
+fn user_cache_macro() {
+	let ret = user_cache![
+		"data/foo.txt",
+		"data/data/"
+	];
+	assert!(ret.is_ok());
+	assert_eq!(ret.ok(), Some(3));
+	let target_dir = user_cache_dir(Some(env!("CARGO_PKG_NAME")), None).unwrap().join(env!("CARGO_PKG_VERSION"));
+	for file in &["foo.txt", "bar.txt", "baz/quux.txt"] {
+		assert!(target_dir.join(file).exists());
+	}
+	remove_dir_all(target_dir).unwrap();
+}
+
+#[test]
+fn user_config_macro_env_override() {
+	let dir = ::std::env::temp_dir().join("assoc_files_test_config_override");
+	::std::env::set_var("ASSOC_FILES_CONFIG_DIR", &dir);
+	let ret = user_config![
+		"data/foo.txt"
+	];
+	::std::env::remove_var("ASSOC_FILES_CONFIG_DIR");
+	assert!(ret.is_ok());
+	assert_eq!(ret.ok(), Some(1));
+	let target_dir = dir.join(env!("CARGO_PKG_VERSION"));
+	assert!(target_dir.join("foo.txt").exists());
+	remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn user_data_uninstall_macro() {
+	let ret = user_data![
+		"data/foo.txt",
+		"data/data/"
+	];
+	assert!(ret.is_ok());
+	let target_dir = user_data_dir(Some(env!("CARGO_PKG_NAME")), None, false).unwrap().join(env!("CARGO_PKG_VERSION"));
+	assert!(target_dir.join("foo.txt").exists());
+
+	let removed = user_data_uninstall!();
+	assert!(removed.is_ok());
+	assert_eq!(removed.ok(), Some(3));
+	for file in &["foo.txt", "bar.txt", "baz/quux.txt"] {
+		assert!(!target_dir.join(file).exists());
+	}
+	//  `baz/` held only the uninstalled file, so it should have been pruned.
+	assert!(!target_dir.join("baz").exists());
+
+	remove_dir_all(target_dir).unwrap();
+}
+
+#[test]
+fn run_config_macro_materializes_once() {
+	//  `include_bytes!` resolves relative to this source file, not the crate
+	//  root, unlike the runtime paths `user_config!` walks. The file still
+	//  lands at `foo.txt`, the same top-level layout `user_config!` gives a
+	//  file argument, not at the nested source path `data/foo.txt`.
+	let ret = run_config!["data/foo.txt"];
+	assert!(ret.is_ok());
+	let target_dir = ret.unwrap();
+	assert!(target_dir.join("foo.txt").exists());
+
+	//  A second call must not disturb a file that is already present.
+	::std::fs::write(target_dir.join("foo.txt"), b"local edit").unwrap();
+	let ret = run_config!["data/foo.txt"];
+	assert!(ret.is_ok());
+	let contents = ::std::fs::read(target_dir.join("foo.txt")).unwrap();
+	assert_eq!(contents, b"local edit");
+
+	remove_dir_all(target_dir).unwrap();
+}